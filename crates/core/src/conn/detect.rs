@@ -0,0 +1,368 @@
+//! DetectingListener and it's implements.
+use std::io::{self, Result as IoResult};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use bytes::BytesMut;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, ReadBuf};
+use tokio::sync::mpsc;
+
+use crate::async_trait;
+use crate::conn::Holding;
+use crate::conn::HttpBuilders;
+use crate::http::{HttpConnection, Version};
+use crate::service::HyperHandler;
+
+use super::rt::{self, Either};
+use super::{Accepted, Acceptor, JoinedStream, Listener};
+
+/// The bytes a TLS `ClientHello` record always starts with: the handshake content type
+/// (`0x16`) followed by the major version byte of the legacy record version (`0x03`).
+const TLS_HANDSHAKE_SIGNATURE: [u8; 2] = [0x16, 0x03];
+
+/// Default number of bytes peeked from a new connection to decide if it is TLS or plaintext.
+pub const DEFAULT_SNIFF_LEN: usize = 2;
+/// Default time allowed for the sniff bytes to arrive before the connection is dropped.
+pub const DEFAULT_SNIFF_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A wrapper that can turn a sniffed, already-peeked stream into a TLS connection.
+#[async_trait]
+pub trait TlsWrapper<S> {
+    /// The connection type produced once the TLS handshake completes.
+    type Conn: HttpConnection + AsyncRead + AsyncWrite + Send + Unpin + 'static;
+    /// Perform the TLS handshake over `stream`.
+    async fn wrap(&self, stream: S) -> IoResult<Self::Conn>;
+}
+
+/// A buffering adapter that lets its first few bytes be peeked without consuming them.
+///
+/// [`AsyncRead`] has no `peek`, so [`sniff`](SniffingStream::sniff) reads the sniff bytes into
+/// an internal buffer; the first [`poll_read`](AsyncRead::poll_read) call replays them before
+/// delegating to the inner socket, so both the TLS handshake and the HTTP parser see the full
+/// byte stream.
+pub struct SniffingStream<S> {
+    inner: S,
+    sniffed: BytesMut,
+}
+
+impl<S> SniffingStream<S>
+where
+    S: AsyncRead + Unpin,
+{
+    fn new(inner: S) -> Self {
+        SniffingStream {
+            inner,
+            sniffed: BytesMut::new(),
+        }
+    }
+
+    /// Buffer at least `len` bytes from the underlying stream (fewer if it ends first) without
+    /// consuming them for the next reader, and return the first `len` of them. The whole wait is
+    /// bounded by `sniff_timeout`.
+    async fn sniff(&mut self, len: usize, sniff_timeout: Duration) -> IoResult<&[u8]> {
+        let inner = &mut self.inner;
+        let sniffed = &mut self.sniffed;
+        rt::timeout(sniff_timeout, async {
+            while sniffed.len() < len {
+                let mut buf = [0_u8; 64];
+                let read = inner.read(&mut buf).await?;
+                if read == 0 {
+                    break;
+                }
+                sniffed.extend_from_slice(&buf[..read]);
+            }
+            Ok::<_, io::Error>(())
+        })
+        .await??;
+        Ok(&self.sniffed[..self.sniffed.len().min(len)])
+    }
+}
+
+impl<S> AsyncRead for SniffingStream<S>
+where
+    S: AsyncRead + Unpin,
+{
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        if !this.sniffed.is_empty() {
+            let len = this.sniffed.len().min(buf.remaining());
+            buf.put_slice(&this.sniffed[..len]);
+            let _ = this.sniffed.split_to(len);
+            return Poll::Ready(Ok(()));
+        }
+        Pin::new(&mut this.inner).poll_read(cx, buf)
+    }
+}
+
+impl<S> AsyncWrite for SniffingStream<S>
+where
+    S: AsyncWrite + Unpin,
+{
+    #[inline]
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().inner).poll_write(cx, buf)
+    }
+
+    #[inline]
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+    }
+
+    #[inline]
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_shutdown(cx)
+    }
+}
+
+#[async_trait]
+impl<S> HttpConnection for SniffingStream<S>
+where
+    S: HttpConnection + Send,
+{
+    async fn version(&mut self) -> Option<Version> {
+        self.inner.version().await
+    }
+    async fn serve(self, handler: HyperHandler, builders: Arc<HttpBuilders>) -> IoResult<()> {
+        self.inner.serve(handler, builders).await
+    }
+}
+
+/// A listener that sniffs the first bytes of each connection and routes it to either the
+/// plaintext path or a TLS acceptor, so HTTP and TLS can be served from the same port.
+pub struct DetectingListener<L, T> {
+    inner: L,
+    tls: T,
+    sniff_len: usize,
+    sniff_timeout: Duration,
+}
+
+impl<L, T> DetectingListener<L, T> {
+    /// Create a new `DetectingListener` wrapping `inner`, dispatching TLS-looking connections
+    /// to `tls`.
+    #[inline]
+    pub fn new(inner: L, tls: T) -> Self {
+        DetectingListener {
+            inner,
+            tls,
+            sniff_len: DEFAULT_SNIFF_LEN,
+            sniff_timeout: DEFAULT_SNIFF_TIMEOUT,
+        }
+    }
+
+    /// Set how many bytes are peeked to decide if a connection is TLS. Defaults to
+    /// [`DEFAULT_SNIFF_LEN`].
+    #[inline]
+    pub fn sniff_len(mut self, sniff_len: usize) -> Self {
+        self.sniff_len = sniff_len;
+        self
+    }
+
+    /// Set how long to wait for the sniff bytes to arrive. Defaults to
+    /// [`DEFAULT_SNIFF_TIMEOUT`].
+    #[inline]
+    pub fn sniff_timeout(mut self, sniff_timeout: Duration) -> Self {
+        self.sniff_timeout = sniff_timeout;
+        self
+    }
+}
+
+#[async_trait]
+impl<L, T> Listener for DetectingListener<L, T>
+where
+    L: Listener + Send + Unpin + 'static,
+    L::Acceptor: Acceptor + Send + Unpin + 'static,
+    T: TlsWrapper<SniffingStream<<L::Acceptor as Acceptor>::Conn>> + Send + Sync + Unpin + 'static,
+{
+    type Acceptor = DetectingAcceptor<L::Acceptor, T>;
+
+    async fn bind(self) -> Self::Acceptor {
+        self.try_bind().await.unwrap()
+    }
+
+    async fn try_bind(self) -> IoResult<Self::Acceptor> {
+        let inner = self.inner.try_bind().await?;
+        let holdings = inner.holdings().to_vec();
+        let (results_tx, results_rx) = mpsc::unbounded_channel();
+        Ok(DetectingAcceptor {
+            inner,
+            tls: Arc::new(self.tls),
+            sniff_len: self.sniff_len,
+            sniff_timeout: self.sniff_timeout,
+            holdings,
+            results_tx,
+            results_rx,
+        })
+    }
+}
+
+/// The connection type produced by a [`DetectingAcceptor`] for a given inner acceptor `A` and
+/// TLS wrapper `T`.
+type DetectedConn<A, T> =
+    JoinedStream<SniffingStream<<A as Acceptor>::Conn>, <T as TlsWrapper<SniffingStream<<A as Acceptor>::Conn>>>::Conn>;
+
+/// Peek `accepted`'s first bytes and route it to the plaintext or TLS branch. Run on its own
+/// spawned task so a slow or silent client only delays its own connection, not the shared
+/// accept loop.
+async fn detect_conn<A, T>(
+    accepted: Accepted<A::Conn>,
+    tls: Arc<T>,
+    sniff_len: usize,
+    sniff_timeout: Duration,
+) -> IoResult<Accepted<DetectedConn<A, T>>>
+where
+    A: Acceptor,
+    A::Conn: AsyncRead + AsyncWrite + Send + Unpin + 'static,
+    T: TlsWrapper<SniffingStream<A::Conn>> + Send + Sync + 'static,
+{
+    let mut accepted = accepted.map_conn(SniffingStream::new);
+    let is_tls = accepted
+        .conn
+        .sniff(sniff_len, sniff_timeout)
+        .await?
+        .starts_with(&TLS_HANDSHAKE_SIGNATURE);
+    if is_tls {
+        let conn = tls.wrap(accepted.conn).await?;
+        Ok(Accepted {
+            conn: JoinedStream::B(conn),
+            ..accepted
+        })
+    } else {
+        Ok(Accepted {
+            conn: JoinedStream::A(accepted.conn),
+            ..accepted
+        })
+    }
+}
+
+/// An acceptor that routes each accepted connection to the plaintext or TLS path based on its
+/// first bytes.
+///
+/// Sniffing happens off the shared accept loop: each raw connection is handed to its own
+/// spawned task, and `accept` fans in whichever task finishes first, so one slow or silent
+/// client can no longer stall every other pending connection.
+pub struct DetectingAcceptor<A, T>
+where
+    A: Acceptor,
+    T: TlsWrapper<SniffingStream<A::Conn>>,
+{
+    inner: A,
+    tls: Arc<T>,
+    sniff_len: usize,
+    sniff_timeout: Duration,
+    holdings: Vec<Holding>,
+    results_tx: mpsc::UnboundedSender<IoResult<Accepted<DetectedConn<A, T>>>>,
+    results_rx: mpsc::UnboundedReceiver<IoResult<Accepted<DetectedConn<A, T>>>>,
+}
+
+#[async_trait]
+impl<A, T> Acceptor for DetectingAcceptor<A, T>
+where
+    A: Acceptor + Send + Unpin + 'static,
+    A::Conn: HttpConnection + AsyncRead + AsyncWrite + Send + Unpin + 'static,
+    T: TlsWrapper<SniffingStream<A::Conn>> + Send + Sync + Unpin + 'static,
+{
+    type Conn = DetectedConn<A, T>;
+
+    #[inline]
+    fn holdings(&self) -> &[Holding] {
+        &self.holdings
+    }
+
+    async fn accept(&mut self) -> IoResult<Accepted<Self::Conn>> {
+        loop {
+            match rt::select2(self.inner.accept(), self.results_rx.recv()).await {
+                Either::Left(accepted) => {
+                    let accepted = accepted?;
+                    let tls = self.tls.clone();
+                    let sniff_len = self.sniff_len;
+                    let sniff_timeout = self.sniff_timeout;
+                    let results_tx = self.results_tx.clone();
+                    rt::spawn(async move {
+                        let _ = results_tx.send(detect_conn::<A, T>(accepted, tls, sniff_len, sniff_timeout).await);
+                    });
+                }
+                Either::Right(result) => {
+                    return result.expect("`self` always holds a live `results_tx`");
+                }
+            }
+        }
+    }
+}
+
+/// Extension trait that adds [`detect`](ListenerExt::detect) to all [`Listener`]s.
+pub trait ListenerExt: Listener + Sized {
+    /// Wrap this listener so each connection is sniffed and routed to the plaintext path or
+    /// `tls`, letting HTTP and TLS be served from the same port. See [`DetectingListener`].
+    fn detect<T>(self, tls: T) -> DetectingListener<Self, T> {
+        DetectingListener::new(self, tls)
+    }
+}
+
+impl<L> ListenerExt for L where L: Listener {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_sniffing_stream_replays_peeked_bytes() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt, duplex};
+
+        let (mut client, server) = duplex(64);
+        client.write_all(&[0x16, 0x03, 0x01, 0x00]).await.unwrap();
+
+        let mut stream = SniffingStream::new(server);
+        let sniffed = stream.sniff(DEFAULT_SNIFF_LEN, DEFAULT_SNIFF_TIMEOUT).await.unwrap();
+        assert_eq!(sniffed, &TLS_HANDSHAKE_SIGNATURE);
+
+        let mut full = [0_u8; 4];
+        stream.read_exact(&mut full).await.unwrap();
+        assert_eq!(full, [0x16, 0x03, 0x01, 0x00]);
+    }
+
+    /// A minimal stand-in connection so the `HttpConnection` dispatch chain can be exercised
+    /// end-to-end without a real socket or TLS handshake.
+    struct MockConn {
+        version: Version,
+    }
+
+    #[async_trait]
+    impl HttpConnection for MockConn {
+        async fn version(&mut self) -> Option<Version> {
+            Some(self.version)
+        }
+        async fn serve(self, _handler: HyperHandler, _builders: Arc<HttpBuilders>) -> IoResult<()> {
+            Ok(())
+        }
+    }
+
+    impl AsyncRead for MockConn {
+        fn poll_read(self: Pin<&mut Self>, _cx: &mut Context<'_>, _buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    impl AsyncWrite for MockConn {
+        fn poll_write(self: Pin<&mut Self>, _cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+            Poll::Ready(Ok(buf.len()))
+        }
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+        fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_http_connection_dispatches_through_both_branches() {
+        let mut plaintext: JoinedStream<SniffingStream<MockConn>, MockConn> =
+            JoinedStream::A(SniffingStream::new(MockConn { version: Version::HTTP11 }));
+        assert_eq!(plaintext.version().await, Some(Version::HTTP11));
+
+        let mut tls: JoinedStream<SniffingStream<MockConn>, MockConn> = JoinedStream::B(MockConn { version: Version::HTTP2 });
+        assert_eq!(tls.version().await, Some(Version::HTTP2));
+    }
+}
@@ -0,0 +1,101 @@
+//! Graceful shutdown for any [`Acceptor`].
+use std::io::{self, Result as IoResult};
+
+use tokio::sync::watch;
+
+use crate::async_trait;
+
+use super::rt::{self, Either};
+use super::{Accepted, Acceptor, Holding};
+
+fn shutdown_error() -> io::Error {
+    io::Error::new(io::ErrorKind::Interrupted, "acceptor is shutting down")
+}
+
+/// Extension trait that adds [`with_graceful_shutdown`](ShutdownAcceptorExt::with_graceful_shutdown)
+/// to every [`Acceptor`] — `JoinedAcceptor`, `VecAcceptor`, `DetectingAcceptor` and any acceptor
+/// built on top of them all get the same shutdown hook this way, without the `Acceptor` trait
+/// itself needing a shutdown-aware method.
+pub trait ShutdownAcceptorExt: Acceptor + Sized {
+    /// Arm a graceful-shutdown signal: once `signal` reports `true`, `accept` stops waiting on
+    /// new connections and resolves to a shutdown error instead, so a caller can stop an
+    /// acceptor while letting already-accepted connections drain on their own.
+    #[inline]
+    fn with_graceful_shutdown(self, signal: watch::Receiver<bool>) -> ShutdownAcceptor<Self> {
+        ShutdownAcceptor {
+            inner: self,
+            shutdown: signal,
+        }
+    }
+}
+
+impl<A> ShutdownAcceptorExt for A where A: Acceptor {}
+
+/// An acceptor that stops accepting once its shutdown signal fires. See
+/// [`ShutdownAcceptorExt::with_graceful_shutdown`].
+pub struct ShutdownAcceptor<A> {
+    inner: A,
+    shutdown: watch::Receiver<bool>,
+}
+
+#[async_trait]
+impl<A> Acceptor for ShutdownAcceptor<A>
+where
+    A: Acceptor + Send + Unpin + 'static,
+{
+    type Conn = A::Conn;
+
+    #[inline]
+    fn holdings(&self) -> &[Holding] {
+        self.inner.holdings()
+    }
+
+    #[inline]
+    async fn accept(&mut self) -> IoResult<Accepted<Self::Conn>> {
+        loop {
+            if *self.shutdown.borrow() {
+                return Err(shutdown_error());
+            }
+            match rt::select2(self.inner.accept(), self.shutdown.changed()).await {
+                Either::Left(accepted) => return accepted,
+                // The value changed; check at the top of the loop whether it is now `true`.
+                Either::Right(Ok(())) => {}
+                // The sender is gone, so the signal can no longer turn `true`.
+                Either::Right(Err(_)) => return self.inner.accept().await,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::net::TcpStream;
+
+    use super::*;
+    use crate::conn::TcpListener;
+
+    #[tokio::test]
+    async fn test_shutdown_acceptor_stops_accepting() {
+        let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 6987));
+
+        let (tx, rx) = watch::channel(false);
+        let mut acceptor = TcpListener::new(addr).bind().await.with_graceful_shutdown(rx);
+
+        tx.send(true).unwrap();
+        let err = acceptor.accept().await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::Interrupted);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_acceptor_still_accepts_before_signal() {
+        let addr = std::net::SocketAddr::from(([127, 0, 0, 1], 6988));
+
+        let (_tx, rx) = watch::channel(false);
+        let mut acceptor = TcpListener::new(addr).bind().await.with_graceful_shutdown(rx);
+
+        tokio::spawn(async move {
+            TcpStream::connect(addr).await.unwrap();
+        });
+        acceptor.accept().await.unwrap();
+    }
+}
@@ -13,6 +13,7 @@ use crate::conn::HttpBuilders;
 use crate::http::{HttpConnection, Version};
 use crate::service::HyperHandler;
 
+use super::rt::{self, Either};
 use super::{Accepted, Acceptor, Listener};
 
 /// A I/O stream for JoinedListener.
@@ -100,8 +101,7 @@ where
     async fn try_bind(self) -> IoResult<Self::Acceptor> {
         let a = self.a.try_bind().await?;
         let b = self.b.try_bind().await?;
-        let holdings = a.holdings().iter().chain(b.holdings().iter()).cloned().collect();
-        Ok(JoinedAcceptor { a, b, holdings })
+        Ok(JoinedAcceptor::new(a, b))
     }
 }
 
@@ -111,6 +111,17 @@ pub struct JoinedAcceptor<A, B> {
     holdings: Vec<Holding>,
 }
 
+impl<A, B> JoinedAcceptor<A, B>
+where
+    A: Acceptor,
+    B: Acceptor,
+{
+    fn new(a: A, b: B) -> Self {
+        let holdings = a.holdings().iter().chain(b.holdings().iter()).cloned().collect();
+        JoinedAcceptor { a, b, holdings }
+    }
+}
+
 #[async_trait]
 impl<A, B> HttpConnection for JoinedStream<A, B>
 where
@@ -148,17 +159,30 @@ where
 
     #[inline]
     async fn accept(&mut self) -> IoResult<Accepted<Self::Conn>> {
-        tokio::select! {
-            accepted = self.a.accept() => {
-                Ok(accepted?.map_conn(JoinedStream::A))
-            }
-            accepted = self.b.accept() => {
-                Ok(accepted?.map_conn(JoinedStream::B))
-            }
+        match rt::select2(self.a.accept(), self.b.accept()).await {
+            Either::Left(accepted) => Ok(accepted?.map_conn(JoinedStream::A)),
+            Either::Right(accepted) => Ok(accepted?.map_conn(JoinedStream::B)),
         }
     }
 }
 
+/// Extension trait that adds [`join`](AcceptorExt::join) to all [`Acceptor`]s.
+///
+/// This mirrors [`Listener::join`], but operates on already-bound acceptors, so listeners can
+/// be bound separately (to inspect each one's [`holdings`](Acceptor::holdings) or bind them at
+/// different times/tasks) and merged into a single accept loop afterwards.
+pub trait AcceptorExt: Acceptor + Sized {
+    /// Joins with another acceptor, producing a [`JoinedAcceptor`] that accepts from both.
+    fn join<T>(self, other: T) -> JoinedAcceptor<Self, T>
+    where
+        T: Acceptor,
+    {
+        JoinedAcceptor::new(self, other)
+    }
+}
+
+impl<A> AcceptorExt for A where A: Acceptor {}
+
 #[cfg(test)]
 mod tests {
     use tokio::io::{ AsyncReadExt, AsyncWriteExt};
@@ -186,4 +210,28 @@ mod tests {
         let second = conn.read_i32().await.unwrap();
         assert_eq!(first + second, 150);
     }
+
+    #[tokio::test]
+    async fn test_acceptor_ext_join() {
+        let addr1 = std::net::SocketAddr::from(([127, 0, 0, 1], 6983));
+        let addr2 = std::net::SocketAddr::from(([127, 0, 0, 1], 6984));
+
+        let a = TcpListener::new(addr1).bind().await;
+        let b = TcpListener::new(addr2).bind().await;
+        let mut acceptor = a.join(b);
+        assert_eq!(acceptor.holdings().len(), 2);
+
+        tokio::spawn(async move {
+            let mut stream = TcpStream::connect(addr1).await.unwrap();
+            stream.write_i32(50).await.unwrap();
+
+            let mut stream = TcpStream::connect(addr2).await.unwrap();
+            stream.write_i32(100).await.unwrap();
+        });
+        let Accepted { mut conn, .. } = acceptor.accept().await.unwrap();
+        let first = conn.read_i32().await.unwrap();
+        let Accepted { mut conn, .. } = acceptor.accept().await.unwrap();
+        let second = conn.read_i32().await.unwrap();
+        assert_eq!(first + second, 150);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,98 @@
+//! Runtime-agnostic helpers so the `conn` layer's accept loops can build on Tokio or async-std.
+//!
+//! Enable `rt-tokio` (the default) or `rt-async-std` to pick the backend for [`spawn`],
+//! [`timeout`] and [`select2`], which [`JoinedAcceptor`]'s and [`DetectingAcceptor`]'s accept
+//! loops build on instead of calling into `tokio` directly.
+//!
+//! This module does not (yet) abstract the connection and stream types themselves — the
+//! `AsyncRead`/`AsyncWrite` impls on [`JoinedStream`], [`SniffingStream`] and [`VecStream`] are
+//! written against `tokio::io`'s `poll_read(&mut ReadBuf<'_>)` shape, which has no equivalent in
+//! `futures_io`'s `AsyncRead`. Porting those is a larger follow-up; until then, `rt-async-std`
+//! only buys portability for the select loops above, not for the stream types built on top of
+//! them.
+//!
+//! [`JoinedAcceptor`]: super::JoinedAcceptor
+//! [`DetectingAcceptor`]: super::DetectingAcceptor
+//! [`JoinedStream`]: super::JoinedStream
+//! [`SniffingStream`]: super::SniffingStream
+//! [`VecStream`]: super::VecStream
+use std::future::Future;
+use std::io;
+use std::time::Duration;
+
+/// The result of [`select2`]: whichever future completed first.
+pub(crate) enum Either<L, R> {
+    Left(L),
+    Right(R),
+}
+
+#[cfg(feature = "rt-tokio")]
+mod backend {
+    use super::*;
+
+    /// Spawn a task on the configured runtime.
+    pub(crate) fn spawn<F>(future: F) -> tokio::task::JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        tokio::spawn(future)
+    }
+
+    /// Bound a future with a timeout.
+    pub(crate) async fn timeout<F: Future>(duration: Duration, future: F) -> io::Result<F::Output> {
+        tokio::time::timeout(duration, future)
+            .await
+            .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "future timed out"))
+    }
+
+    /// Run two futures concurrently, resolving to whichever completes first.
+    pub(crate) async fn select2<A, B>(a: A, b: B) -> Either<A::Output, B::Output>
+    where
+        A: Future,
+        B: Future,
+    {
+        tokio::select! {
+            v = a => Either::Left(v),
+            v = b => Either::Right(v),
+        }
+    }
+
+}
+
+#[cfg(all(feature = "rt-async-std", not(feature = "rt-tokio")))]
+mod backend {
+    use super::*;
+
+    /// Spawn a task on the configured runtime.
+    pub(crate) fn spawn<F>(future: F) -> async_std::task::JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        async_std::task::spawn(future)
+    }
+
+    /// Bound a future with a timeout.
+    pub(crate) async fn timeout<F: Future>(duration: Duration, future: F) -> io::Result<F::Output> {
+        async_std::future::timeout(duration, future)
+            .await
+            .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "future timed out"))
+    }
+
+    /// Run two futures concurrently, resolving to whichever completes first.
+    pub(crate) async fn select2<A, B>(a: A, b: B) -> Either<A::Output, B::Output>
+    where
+        A: Future,
+        B: Future,
+    {
+        futures_util::pin_mut!(a, b);
+        match futures_util::future::select(a, b).await {
+            futures_util::future::Either::Left((v, _)) => Either::Left(v),
+            futures_util::future::Either::Right((v, _)) => Either::Right(v),
+        }
+    }
+
+}
+
+pub(crate) use backend::{select2, spawn, timeout};
@@ -0,0 +1,186 @@
+//! VecListener and it's implements.
+use std::io::{self, Result as IoResult};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use futures_util::future::select_all;
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+use crate::async_trait;
+use crate::conn::Holding;
+use crate::conn::HttpBuilders;
+use crate::http::{HttpConnection, Version};
+use crate::service::HyperHandler;
+
+use super::{Accepted, Acceptor, Listener};
+
+/// An I/O stream for `VecListener`, tagged with the index of the acceptor that produced it.
+pub struct VecStream<C> {
+    index: usize,
+    conn: C,
+}
+
+impl<C> VecStream<C> {
+    /// Index of the acceptor in the joined set that produced this stream.
+    #[inline]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+impl<C> AsyncRead for VecStream<C>
+where
+    C: AsyncRead + Send + Unpin + 'static,
+{
+    #[inline]
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().conn).poll_read(cx, buf)
+    }
+}
+
+impl<C> AsyncWrite for VecStream<C>
+where
+    C: AsyncWrite + Send + Unpin + 'static,
+{
+    #[inline]
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().conn).poll_write(cx, buf)
+    }
+
+    #[inline]
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().conn).poll_flush(cx)
+    }
+
+    #[inline]
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().conn).poll_shutdown(cx)
+    }
+}
+
+#[async_trait]
+impl<C> HttpConnection for VecStream<C>
+where
+    C: HttpConnection + Send,
+{
+    async fn version(&mut self) -> Option<Version> {
+        self.conn.version().await
+    }
+    async fn serve(self, handler: HyperHandler, builders: Arc<HttpBuilders>) -> IoResult<()> {
+        self.conn.serve(handler, builders).await
+    }
+}
+
+/// A listener that joins an arbitrary number of listeners of the same type, keeping the
+/// resulting [`Acceptor`]'s type flat regardless of how many endpoints are bound.
+///
+/// Unlike [`JoinedListener`](super::JoinedListener), which nests two listener types together,
+/// `VecListener` holds a single `Vec<L>`, so binding ten endpoints looks the same as binding
+/// two.
+pub struct VecListener<L> {
+    listeners: Vec<L>,
+}
+
+impl<L> VecListener<L> {
+    /// Create a new `VecListener` from a list of listeners.
+    #[inline]
+    pub fn new(listeners: Vec<L>) -> Self {
+        VecListener { listeners }
+    }
+}
+
+#[async_trait]
+impl<L> Listener for VecListener<L>
+where
+    L: Listener + Send + Unpin + 'static,
+    L::Acceptor: Acceptor + Send + Unpin + 'static,
+{
+    type Acceptor = VecAcceptor<L::Acceptor>;
+
+    async fn bind(self) -> Self::Acceptor {
+        self.try_bind().await.unwrap()
+    }
+
+    async fn try_bind(self) -> IoResult<Self::Acceptor> {
+        if self.listeners.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "VecListener requires at least one listener",
+            ));
+        }
+        let mut acceptors = Vec::with_capacity(self.listeners.len());
+        for listener in self.listeners {
+            acceptors.push(listener.try_bind().await?);
+        }
+        let holdings = acceptors.iter().flat_map(|a| a.holdings().iter().cloned()).collect();
+        Ok(VecAcceptor { acceptors, holdings })
+    }
+}
+
+/// An acceptor that fans in accepts across a `Vec` of acceptors with a single
+/// [`select_all`](futures_util::future::select_all).
+pub struct VecAcceptor<A> {
+    acceptors: Vec<A>,
+    holdings: Vec<Holding>,
+}
+
+#[async_trait]
+impl<A> Acceptor for VecAcceptor<A>
+where
+    A: Acceptor + Send + Unpin + 'static,
+    A::Conn: HttpConnection + AsyncRead + AsyncWrite + Send + Unpin + 'static,
+{
+    type Conn = VecStream<A::Conn>;
+
+    #[inline]
+    fn holdings(&self) -> &[Holding] {
+        &self.holdings
+    }
+
+    #[inline]
+    async fn accept(&mut self) -> IoResult<Accepted<Self::Conn>> {
+        let futures = self.acceptors.iter_mut().map(|acceptor| Box::pin(acceptor.accept()));
+        let (accepted, index, _) = select_all(futures).await;
+        Ok(accepted?.map_conn(|conn| VecStream { index, conn }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+
+    use super::*;
+    use crate::conn::TcpListener;
+
+    #[tokio::test]
+    async fn test_vec_listener() {
+        let addr1 = std::net::SocketAddr::from(([127, 0, 0, 1], 6980));
+        let addr2 = std::net::SocketAddr::from(([127, 0, 0, 1], 6981));
+        let addr3 = std::net::SocketAddr::from(([127, 0, 0, 1], 6982));
+
+        let mut acceptor = VecListener::new(vec![
+            TcpListener::new(addr1),
+            TcpListener::new(addr2),
+            TcpListener::new(addr3),
+        ])
+        .bind()
+        .await;
+        assert_eq!(acceptor.holdings().len(), 3);
+
+        tokio::spawn(async move {
+            let mut stream = TcpStream::connect(addr2).await.unwrap();
+            stream.write_i32(100).await.unwrap();
+        });
+        let Accepted { mut conn, .. } = acceptor.accept().await.unwrap();
+        assert_eq!(conn.index(), 1);
+        assert_eq!(conn.read_i32().await.unwrap(), 100);
+    }
+
+    #[tokio::test]
+    async fn test_vec_listener_rejects_empty_vec() {
+        let err = VecListener::<TcpListener>::new(vec![]).try_bind().await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+}